@@ -1,68 +1,494 @@
 use std::cmp::max;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt;
 use std::fs::OpenOptions;
-use std::io::{Read, Seek, SeekFrom, Write};
 
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use log::info;
 
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
 use serde::{Deserialize, Serialize};
 
-const TABLE_MAX_PAGES: usize = 100;
 const PAGE_SIZE: usize = 4096;
 
+/// Largest page size a `select first n` query may request.
+const MAX_PAGE_SIZE: usize = 1000;
+
 #[derive(Clone)]
 struct Page {
     bytes: [u8; PAGE_SIZE],
-    end_offset: usize,
 }
 
 impl Page {
     fn new(data: [u8; PAGE_SIZE]) -> Self {
-        Self {
-            bytes: data,
-            end_offset: 0,
+        Self { bytes: data }
+    }
+}
+
+// --- B+tree node layout -----------------------------------------------
+//
+// Every page is either an internal node or a leaf node. Page 0 is reserved
+// for table metadata (see the page 0 layout below) and is never a node.
+//
+// Header (common to both node types), 11 bytes:
+//   offset 0      node type (0 = internal, 1 = leaf)
+//   offset 1..5   parent page id (0 = none, i.e. this is the root)
+//   offset 5..7   cell count
+//   offset 7..11  leaf: next-leaf page id (0 = none)
+//                 internal: rightmost child page id
+//
+// Leaf cells are `(key: u32, serialized Row)`, sorted by key.
+// Internal cells are `(child page id: u32, key: u32)`: the child holds all
+// keys strictly less than `key`; keys greater than every cell's key live in
+// the rightmost child.
+
+const NODE_TYPE_INTERNAL: u8 = 0;
+const NODE_TYPE_LEAF: u8 = 1;
+
+const NODE_HEADER_SIZE: usize = 11;
+const NODE_TYPE_OFFSET: usize = 0;
+const NODE_PARENT_OFFSET: usize = 1;
+const NODE_CELL_COUNT_OFFSET: usize = 5;
+const NODE_EXTRA_OFFSET: usize = 7;
+const NODE_CELLS_OFFSET: usize = NODE_HEADER_SIZE;
+
+const INTERNAL_CELL_SIZE: usize = 8; // child page id (4) + key (4)
+
+// Page 0 is the metadata page, so 0 is never a real node's id and doubles
+// as the "no page" sentinel for parent/next-leaf/rightmost-child pointers.
+const NO_PAGE: u32 = 0;
+
+// --- Page 0: table metadata ---------------------------------------------
+//
+//   offset 0..8    magic bytes, identifies an already-initialized table
+//   offset 8       format version
+//   offset 9..13   root page id
+//   offset 13..17  free-page-list head (0 = empty)
+//   offset 17..21  index catalog page id (0 = no indexes created yet)
+//   offset 21      zone map overflowed flag (1 = an entry was ever dropped
+//                   for lack of room; see `zone_map_set`)
+//   offset 22..24  zone map entry count
+//   offset 24..    zone map entries, `ZONE_MAP_MAX_ENTRIES` of them: one
+//                   `(leaf page id: u32, min id: u32, max id: u32)` triple
+//                   per leaf page, so range scans can skip leaves whose
+//                   interval can't overlap the query (see `select where`)
+
+const METADATA_MAGIC: [u8; 8] = *b"RSDBPAGE";
+const METADATA_MAGIC_OFFSET: usize = 0;
+const METADATA_VERSION_OFFSET: usize = 8;
+const METADATA_VERSION: u8 = 1;
+const METADATA_ROOT_OFFSET: usize = 9;
+const METADATA_FREE_LIST_OFFSET: usize = 13;
+const METADATA_INDEX_CATALOG_OFFSET: usize = 17;
+const ZONE_MAP_OVERFLOWED_OFFSET: usize = 21;
+const ZONE_MAP_COUNT_OFFSET: usize = 22;
+const ZONE_MAP_ENTRIES_OFFSET: usize = 24;
+const ZONE_MAP_ENTRY_SIZE: usize = 12; // page id (4) + min id (4) + max id (4)
+const ZONE_MAP_MAX_ENTRIES: usize = (PAGE_SIZE - ZONE_MAP_ENTRIES_OFFSET) / ZONE_MAP_ENTRY_SIZE;
+
+// --- Secondary hash index pages ------------------------------------------
+//
+// `create index on <column>` reserves `INDEX_BUCKET_COUNT` bucket pages up
+// front. A value is looked up by hashing it (Murmur3) and taking
+// `hash % INDEX_BUCKET_COUNT` to pick the bucket's first page; a bucket page
+// holds fixed-size `(hash: u32, row_id: u32)` reference slots, appended in
+// insertion order (a delete moves the page's last slot into the freed one)
+// and, once full, chains to an overflow page (an ordinary page,
+// allocated the same way a B+tree node would be) via its own header.
+// Indexed values are hashed after the same truncation `Row::new` already
+// applies (`COLUMN_USERNAME_SIZE`/`COLUMN_EMAIL_SIZE`), so that's the
+// effective max key length — anything longer collides with its truncated
+// prefix exactly as two inserted rows would.
+//
+//   offset 0      page type marker (`INDEX_PAGE_MARKER`)
+//   offset 1..3   slot count
+//   offset 3..7   overflow page id (0 = none)
+//   offset 7..    slots, `INDEX_SLOT_SIZE` bytes each: (hash: u32, row_id: u32)
+
+const INDEX_PAGE_MARKER: u8 = 2;
+const INDEX_PAGE_HEADER_SIZE: usize = 7;
+const INDEX_SLOT_SIZE: usize = 8;
+const INDEX_BUCKET_COUNT: usize = 16;
+
+fn index_max_slots() -> usize {
+    (PAGE_SIZE - INDEX_PAGE_HEADER_SIZE) / INDEX_SLOT_SIZE
+}
+
+// --- Index catalog page ---------------------------------------------------
+//
+// Lazily allocated the first time `create index` runs; its page id is
+// recorded at `METADATA_INDEX_CATALOG_OFFSET` on page 0. Holds one entry per
+// live index:
+//
+//   offset 0   index count
+//   entries, `INDEX_CATALOG_ENTRY_SIZE` bytes each:
+//     offset 0     indexed column discriminant
+//     offset 1..   `INDEX_BUCKET_COUNT` bucket page ids (u32 each)
+
+const INDEX_CATALOG_COUNT_OFFSET: usize = 0;
+const INDEX_CATALOG_ENTRIES_OFFSET: usize = 1;
+const INDEX_CATALOG_ENTRY_SIZE: usize = 1 + INDEX_BUCKET_COUNT * 4;
+const INDEX_CATALOG_MAX_ENTRIES: usize =
+    (PAGE_SIZE - INDEX_CATALOG_ENTRIES_OFFSET) / INDEX_CATALOG_ENTRY_SIZE;
+
+// --- Rollback journal ----------------------------------------------------
+//
+// While a transaction is active, the first time `load_page` touches a page,
+// its pre-transaction bytes (the cached copy if the page is already in
+// memory, else what is on disk, or zeros for a page past the end of the
+// file) are appended here before anything can overwrite them, so `rollback`
+// can put them back.
+// `commit` truncates the journal back to empty instead of replaying it.
+// A non-empty journal found when the pager is constructed means the last
+// transaction never reached `commit`, so it's replayed (i.e. undone) before
+// the table is opened.
+//
+//   offset 0    record count
+//   records, `JOURNAL_RECORD_SIZE` bytes each:
+//     offset 0       page id
+//     offset 4..     that page's pre-transaction bytes
+
+const JOURNAL_HEADER_SIZE: usize = 4;
+const JOURNAL_RECORD_SIZE: usize = 4 + PAGE_SIZE; // page id (4) + page bytes
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn write_u32(bytes: &mut [u8], offset: usize, value: u32) {
+    bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn write_u16(bytes: &mut [u8], offset: usize, value: u16) {
+    bytes[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+/// MurmurHash3 (32-bit, x86 variant) over `data`, seeded with `seed`. A
+/// fast, stable, non-cryptographic hash — good enough to bucket secondary
+/// index keys without pulling in an external hashing crate.
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let mut hash = seed;
+    let chunks = data.chunks_exact(4);
+    let remainder = chunks.remainder();
+
+    for chunk in chunks {
+        let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    if !remainder.is_empty() {
+        let mut k = 0u32;
+        for (i, &byte) in remainder.iter().enumerate() {
+            k |= (byte as u32) << (8 * i);
         }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
     }
 
-    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), &'static str> {
-        if data.len() + offset > PAGE_SIZE {
-            Err("not enough space to write")
-        } else {
-            self.bytes[offset..offset + data.len()].copy_from_slice(data);
-            self.end_offset = max(offset + data.len(), self.end_offset);
-            Ok(())
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^= hash >> 16;
+
+    hash
+}
+
+impl Page {
+    fn node_type(&self) -> u8 {
+        self.bytes[NODE_TYPE_OFFSET]
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.node_type() == NODE_TYPE_LEAF
+    }
+
+    fn parent(&self) -> u32 {
+        read_u32(&self.bytes, NODE_PARENT_OFFSET)
+    }
+
+    fn set_parent(&mut self, page_id: u32) {
+        write_u32(&mut self.bytes, NODE_PARENT_OFFSET, page_id);
+    }
+
+    fn cell_count(&self) -> usize {
+        read_u16(&self.bytes, NODE_CELL_COUNT_OFFSET) as usize
+    }
+
+    fn set_cell_count(&mut self, count: usize) {
+        write_u16(&mut self.bytes, NODE_CELL_COUNT_OFFSET, count as u16);
+    }
+
+    fn next_leaf(&self) -> u32 {
+        read_u32(&self.bytes, NODE_EXTRA_OFFSET)
+    }
+
+    fn set_next_leaf(&mut self, page_id: u32) {
+        write_u32(&mut self.bytes, NODE_EXTRA_OFFSET, page_id);
+    }
+
+    fn right_child(&self) -> u32 {
+        read_u32(&self.bytes, NODE_EXTRA_OFFSET)
+    }
+
+    fn set_right_child(&mut self, page_id: u32) {
+        write_u32(&mut self.bytes, NODE_EXTRA_OFFSET, page_id);
+    }
+
+    fn init_leaf(&mut self) {
+        self.bytes[NODE_TYPE_OFFSET] = NODE_TYPE_LEAF;
+        self.set_parent(NO_PAGE);
+        self.set_cell_count(0);
+        self.set_next_leaf(NO_PAGE);
+    }
+
+    fn init_internal(&mut self) {
+        self.bytes[NODE_TYPE_OFFSET] = NODE_TYPE_INTERNAL;
+        self.set_parent(NO_PAGE);
+        self.set_cell_count(0);
+        self.set_right_child(NO_PAGE);
+    }
+
+    fn init_index_page(&mut self) {
+        self.bytes[0] = INDEX_PAGE_MARKER;
+        self.set_index_slot_count(0);
+        self.set_index_overflow(NO_PAGE);
+    }
+
+    fn index_slot_count(&self) -> usize {
+        read_u16(&self.bytes, 1) as usize
+    }
+
+    fn set_index_slot_count(&mut self, count: usize) {
+        write_u16(&mut self.bytes, 1, count as u16);
+    }
+
+    fn index_overflow(&self) -> u32 {
+        read_u32(&self.bytes, 3)
+    }
+
+    fn set_index_overflow(&mut self, page_id: u32) {
+        write_u32(&mut self.bytes, 3, page_id);
+    }
+
+    fn index_slot_offset(index: usize) -> usize {
+        INDEX_PAGE_HEADER_SIZE + index * INDEX_SLOT_SIZE
+    }
+
+    fn index_slot(&self, index: usize) -> (u32, u32) {
+        let offset = Self::index_slot_offset(index);
+        (
+            read_u32(&self.bytes, offset),
+            read_u32(&self.bytes, offset + 4),
+        )
+    }
+
+    fn set_index_slot(&mut self, index: usize, hash: u32, row_id: u32) {
+        let offset = Self::index_slot_offset(index);
+        write_u32(&mut self.bytes, offset, hash);
+        write_u32(&mut self.bytes, offset + 4, row_id);
+    }
+
+    fn internal_cell_offset(index: usize) -> usize {
+        NODE_CELLS_OFFSET + index * INTERNAL_CELL_SIZE
+    }
+
+    fn internal_child(&self, index: usize) -> u32 {
+        read_u32(&self.bytes, Self::internal_cell_offset(index))
+    }
+
+    fn internal_key(&self, index: usize) -> u32 {
+        read_u32(&self.bytes, Self::internal_cell_offset(index) + 4)
+    }
+
+    fn set_internal_cell(&mut self, index: usize, child: u32, key: u32) {
+        let offset = Self::internal_cell_offset(index);
+        write_u32(&mut self.bytes, offset, child);
+        write_u32(&mut self.bytes, offset + 4, key);
+    }
+
+    fn leaf_cell_offset(index: usize, row_size: usize) -> usize {
+        NODE_CELLS_OFFSET + index * (4 + row_size)
+    }
+
+    fn leaf_key(&self, index: usize, row_size: usize) -> u32 {
+        read_u32(&self.bytes, Self::leaf_cell_offset(index, row_size))
+    }
+
+    fn leaf_row_bytes(&self, index: usize, row_size: usize) -> &[u8] {
+        let offset = Self::leaf_cell_offset(index, row_size) + 4;
+        &self.bytes[offset..offset + row_size]
+    }
+
+    fn set_leaf_cell(&mut self, index: usize, key: u32, row_bytes: &[u8], row_size: usize) {
+        let offset = Self::leaf_cell_offset(index, row_size);
+        write_u32(&mut self.bytes, offset, key);
+        self.bytes[offset + 4..offset + 4 + row_size].copy_from_slice(row_bytes);
+    }
+}
+
+/// Positional I/O on the backing store: reads and writes address an offset
+/// directly rather than going through a shared cursor, so a short transfer
+/// can't silently corrupt a page and callers never need to seek first.
+trait RW {
+    fn len(&self) -> Result<u64, &'static str>;
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> Result<(), &'static str>;
+    fn write_all_at(&mut self, buf: &[u8], offset: u64) -> Result<(), &'static str>;
+    /// Forces any buffered writes out to durable storage. A no-op for the
+    /// in-memory test backend, which has nothing to flush to.
+    fn sync(&self) -> Result<(), &'static str>;
+}
+
+#[cfg(unix)]
+impl RW for std::fs::File {
+    fn len(&self) -> Result<u64, &'static str> {
+        self.metadata().map(|m| m.len()).map_err(|_| "failed to stat file")
+    }
+
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> Result<(), &'static str> {
+        std::os::unix::fs::FileExt::read_exact_at(self, buf, offset)
+            .map_err(|_| "failed to read file")
+    }
+
+    fn write_all_at(&mut self, buf: &[u8], offset: u64) -> Result<(), &'static str> {
+        std::os::unix::fs::FileExt::write_all_at(self, buf, offset)
+            .map_err(|_| "failed to write file")
+    }
+
+    fn sync(&self) -> Result<(), &'static str> {
+        self.sync_all().map_err(|_| "failed to sync file")
+    }
+}
+
+// `seek_read`/`seek_write` are short-transfer primitives on Windows (unlike
+// Unix's `pread`/`pwrite`-backed `read_exact_at`/`write_all_at`), so we loop
+// until the whole buffer has moved.
+#[cfg(windows)]
+impl RW for std::fs::File {
+    fn len(&self) -> Result<u64, &'static str> {
+        self.metadata().map(|m| m.len()).map_err(|_| "failed to stat file")
+    }
+
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> Result<(), &'static str> {
+        use std::os::windows::fs::FileExt;
+        let mut done = 0;
+        while done < buf.len() {
+            let n = self
+                .seek_read(&mut buf[done..], offset + done as u64)
+                .map_err(|_| "failed to read file")?;
+            if n == 0 {
+                return Err("failed to read file");
+            }
+            done += n;
         }
+        Ok(())
+    }
+
+    fn write_all_at(&mut self, buf: &[u8], offset: u64) -> Result<(), &'static str> {
+        use std::os::windows::fs::FileExt;
+        let mut done = 0;
+        while done < buf.len() {
+            let n = self
+                .seek_write(&buf[done..], offset + done as u64)
+                .map_err(|_| "failed to write file")?;
+            if n == 0 {
+                return Err("failed to write file");
+            }
+            done += n;
+        }
+        Ok(())
+    }
+
+    fn sync(&self) -> Result<(), &'static str> {
+        self.sync_all().map_err(|_| "failed to sync file")
     }
 }
 
-trait RW: Read + Write + Seek {}
-impl<T: Read + Write + Seek> RW for T {}
+/// Fallback backend for the in-memory `Cursor`-based test path, where there
+/// is no OS file descriptor to hand a positional-I/O syscall.
+impl RW for std::io::Cursor<Vec<u8>> {
+    fn len(&self) -> Result<u64, &'static str> {
+        Ok(self.get_ref().len() as u64)
+    }
+
+    fn read_exact_at(&self, buf: &mut [u8], offset: u64) -> Result<(), &'static str> {
+        let data = self.get_ref();
+        let start = offset as usize;
+        let end = start + buf.len();
+        if end > data.len() {
+            return Err("failed to read file");
+        }
+        buf.copy_from_slice(&data[start..end]);
+        Ok(())
+    }
+
+    fn write_all_at(&mut self, buf: &[u8], offset: u64) -> Result<(), &'static str> {
+        let start = offset as usize;
+        let end = start + buf.len();
+        let data = self.get_mut();
+        if end > data.len() {
+            data.resize(end, 0);
+        }
+        data[start..end].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn sync(&self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
 
 struct Pager {
     file: Box<dyn RW>,
     file_size: usize,
     pages: Vec<Option<Page>>,
+    next_page_id: usize,
+    journal: Box<dyn RW>,
+    journal_record_count: usize,
+    in_transaction: bool,
+    /// Pages already journaled during the current transaction (brand-new
+    /// pages included), so each is only journaled once.
+    journaled_pages: HashSet<usize>,
 }
 
 impl Pager {
-    fn new(mut file: Box<dyn RW>) -> Self {
-        let file_size = file.seek(SeekFrom::End(0)).unwrap() as usize;
-        file.seek(SeekFrom::Start(0)).unwrap();
-        Self {
+    fn new(file: Box<dyn RW>, journal: Box<dyn RW>) -> Self {
+        let file_size = file.len().unwrap_or(0) as usize;
+        let mut pager = Self {
             file,
             file_size,
-            pages: vec![None; TABLE_MAX_PAGES],
-        }
+            pages: Vec::new(),
+            next_page_id: 0,
+            journal,
+            journal_record_count: 0,
+            in_transaction: false,
+            journaled_pages: HashSet::new(),
+        };
+        // A non-empty journal at startup means the last transaction was cut
+        // short before `commit`; undo it before the table is opened.
+        let _ = pager.replay_journal();
+        pager.next_page_id = pager.get_nb_pages().0;
+        pager
     }
 
     fn flush(&mut self, page_id: usize) {
         let offset = (PAGE_SIZE * page_id) as u64;
 
         if let Some(page) = self.pages[page_id].as_mut() {
-            let _ = self.file.seek(SeekFrom::Start(offset));
-            let _ = self.file.write(&page.bytes[0..page.end_offset]);
+            let _ = self.file.write_all_at(&page.bytes, offset);
         }
     }
 
@@ -78,22 +504,291 @@ impl Pager {
     fn load_page(&mut self, page_id: usize) -> Result<&mut Page, &'static str> {
         let (num_pages, _) = self.get_nb_pages();
 
+        if page_id >= self.pages.len() {
+            self.pages.resize(page_id + 1, None);
+        }
+
         if self.pages[page_id].is_none() {
             let mut data = [0x0; PAGE_SIZE];
-            if page_id + 1 < num_pages {
-                // + 1 because pages are indexed from 0
+            if page_id < num_pages {
                 let offset = page_id as u64 * PAGE_SIZE as u64;
-                let _ = self.file.seek(SeekFrom::Start(offset));
-                if self.file.read(&mut data).is_err() {
-                    return Err("Failed to read file");
-                }
+                self.file.read_exact_at(&mut data, offset)?;
             }
 
             self.pages[page_id] = Some(Page::new(data));
         }
 
+        if self.in_transaction && self.journaled_pages.insert(page_id) {
+            let bytes = self.pages[page_id].as_ref().unwrap().bytes;
+            self.append_journal_record(page_id, &bytes)?;
+        }
+
         Ok((self.pages[page_id]).as_mut().unwrap())
     }
+
+    /// Hands out a fresh, zeroed page id, preferring a reclaimed page off
+    /// the free list over growing the file.
+    fn allocate_page(&mut self) -> Result<usize, &'static str> {
+        let head = self.free_list_head()?;
+        if head != NO_PAGE {
+            let page_id = head as usize;
+            let next_free = read_u32(&self.load_page(page_id)?.bytes, 0);
+            self.set_free_list_head(next_free)?;
+            self.load_page(page_id)?.bytes = [0u8; PAGE_SIZE];
+            return Ok(page_id);
+        }
+
+        let page_id = max(self.next_page_id, self.pages.len());
+        self.next_page_id = page_id + 1;
+        self.load_page(page_id)?;
+        Ok(page_id)
+    }
+
+    /// Returns a page to the free list; its first four bytes are
+    /// repurposed to store the next free page id.
+    fn free_page(&mut self, page_id: usize) -> Result<(), &'static str> {
+        let head = self.free_list_head()?;
+        write_u32(&mut self.load_page(page_id)?.bytes, 0, head);
+        self.set_free_list_head(page_id as u32)
+    }
+
+    fn is_initialized(&mut self) -> Result<bool, &'static str> {
+        let page = self.load_page(0)?;
+        Ok(page.bytes[METADATA_MAGIC_OFFSET..METADATA_MAGIC_OFFSET + METADATA_MAGIC.len()]
+            == METADATA_MAGIC)
+    }
+
+    fn root_page_id(&mut self) -> Result<usize, &'static str> {
+        Ok(read_u32(&self.load_page(0)?.bytes, METADATA_ROOT_OFFSET) as usize)
+    }
+
+    fn set_root_page_id(&mut self, page_id: usize) -> Result<(), &'static str> {
+        write_u32(
+            &mut self.load_page(0)?.bytes,
+            METADATA_ROOT_OFFSET,
+            page_id as u32,
+        );
+        Ok(())
+    }
+
+    fn free_list_head(&mut self) -> Result<u32, &'static str> {
+        Ok(read_u32(&self.load_page(0)?.bytes, METADATA_FREE_LIST_OFFSET))
+    }
+
+    fn set_free_list_head(&mut self, page_id: u32) -> Result<(), &'static str> {
+        write_u32(
+            &mut self.load_page(0)?.bytes,
+            METADATA_FREE_LIST_OFFSET,
+            page_id,
+        );
+        Ok(())
+    }
+
+    fn index_catalog_page_id(&mut self) -> Result<u32, &'static str> {
+        Ok(read_u32(
+            &self.load_page(0)?.bytes,
+            METADATA_INDEX_CATALOG_OFFSET,
+        ))
+    }
+
+    fn set_index_catalog_page_id(&mut self, page_id: u32) -> Result<(), &'static str> {
+        write_u32(
+            &mut self.load_page(0)?.bytes,
+            METADATA_INDEX_CATALOG_OFFSET,
+            page_id,
+        );
+        Ok(())
+    }
+
+    fn zone_map_entry_offset(index: usize) -> usize {
+        ZONE_MAP_ENTRIES_OFFSET + index * ZONE_MAP_ENTRY_SIZE
+    }
+
+    fn zone_map_entries(&mut self) -> Result<Vec<(usize, u32, u32)>, &'static str> {
+        let page = self.load_page(0)?;
+        let count = read_u16(&page.bytes, ZONE_MAP_COUNT_OFFSET) as usize;
+        Ok((0..count)
+            .map(|i| {
+                let offset = Self::zone_map_entry_offset(i);
+                (
+                    read_u32(&page.bytes, offset) as usize,
+                    read_u32(&page.bytes, offset + 4),
+                    read_u32(&page.bytes, offset + 8),
+                )
+            })
+            .collect())
+    }
+
+    /// Records the `[min, max]` id interval held by `page_id`, overwriting
+    /// its existing entry if present. Once the fixed-size zone map on page 0
+    /// is full, further updates are silently dropped and `zone_map_overflowed`
+    /// latches true; `select where` then falls back to a full leaf walk so
+    /// this only costs the skip optimization, never rows.
+    fn zone_map_set(&mut self, page_id: usize, min: u32, max: u32) -> Result<(), &'static str> {
+        let mut entries = self.zone_map_entries()?;
+        match entries.iter().position(|&(id, _, _)| id == page_id) {
+            Some(i) => entries[i] = (page_id, min, max),
+            None => {
+                if entries.len() >= ZONE_MAP_MAX_ENTRIES {
+                    return self.set_zone_map_overflowed();
+                }
+                entries.push((page_id, min, max));
+            }
+        }
+        self.zone_map_write(&entries)
+    }
+
+    fn zone_map_overflowed(&mut self) -> Result<bool, &'static str> {
+        Ok(self.load_page(0)?.bytes[ZONE_MAP_OVERFLOWED_OFFSET] != 0)
+    }
+
+    fn set_zone_map_overflowed(&mut self) -> Result<(), &'static str> {
+        self.load_page(0)?.bytes[ZONE_MAP_OVERFLOWED_OFFSET] = 1;
+        Ok(())
+    }
+
+    fn zone_map_remove(&mut self, page_id: usize) -> Result<(), &'static str> {
+        let mut entries = self.zone_map_entries()?;
+        entries.retain(|&(id, _, _)| id != page_id);
+        self.zone_map_write(&entries)
+    }
+
+    fn zone_map_write(&mut self, entries: &[(usize, u32, u32)]) -> Result<(), &'static str> {
+        let page = self.load_page(0)?;
+        write_u16(&mut page.bytes, ZONE_MAP_COUNT_OFFSET, entries.len() as u16);
+        for (i, &(page_id, min, max)) in entries.iter().enumerate() {
+            let offset = Self::zone_map_entry_offset(i);
+            write_u32(&mut page.bytes, offset, page_id as u32);
+            write_u32(&mut page.bytes, offset + 4, min);
+            write_u32(&mut page.bytes, offset + 8, max);
+        }
+        Ok(())
+    }
+
+    /// Sets up a brand-new, empty table: page 0 holds the metadata (magic,
+    /// version, root page id, free list head, zone map) and page 1 is an
+    /// empty leaf root.
+    fn bootstrap(&mut self) -> Result<(), &'static str> {
+        {
+            let page = self.load_page(0)?;
+            page.bytes[METADATA_MAGIC_OFFSET..METADATA_MAGIC_OFFSET + METADATA_MAGIC.len()]
+                .copy_from_slice(&METADATA_MAGIC);
+            page.bytes[METADATA_VERSION_OFFSET] = METADATA_VERSION;
+        }
+        self.set_free_list_head(NO_PAGE)?;
+        self.zone_map_write(&[])?;
+        self.load_page(1)?.init_leaf();
+        self.set_root_page_id(1)
+    }
+
+    fn journal_record_offset(index: usize) -> u64 {
+        (JOURNAL_HEADER_SIZE + index * JOURNAL_RECORD_SIZE) as u64
+    }
+
+    fn journal_record_count(&self) -> Result<usize, &'static str> {
+        if self.journal.len()? < JOURNAL_HEADER_SIZE as u64 {
+            return Ok(0);
+        }
+        let mut header = [0u8; JOURNAL_HEADER_SIZE];
+        self.journal.read_exact_at(&mut header, 0)?;
+        Ok(read_u32(&header, 0) as usize)
+    }
+
+    fn append_journal_record(&mut self, page_id: usize, bytes: &[u8; PAGE_SIZE]) -> Result<(), &'static str> {
+        let offset = Self::journal_record_offset(self.journal_record_count);
+        self.journal.write_all_at(&(page_id as u32).to_le_bytes(), offset)?;
+        self.journal.write_all_at(bytes, offset + 4)?;
+        self.journal_record_count += 1;
+        self.journal
+            .write_all_at(&(self.journal_record_count as u32).to_le_bytes(), 0)
+    }
+
+    /// Resets the journal to empty without touching the data file. Syncs the
+    /// journal so the truncation itself is durable: without this, a crash
+    /// between the data file's fsync in `commit` and this header write
+    /// landing on disk would leave a journal that still looks valid, and
+    /// replaying it on restart would undo an already-committed transaction.
+    fn truncate_journal(&mut self) -> Result<(), &'static str> {
+        self.journal_record_count = 0;
+        self.journal.write_all_at(&0u32.to_le_bytes(), 0)?;
+        self.journal.sync()
+    }
+
+    /// Reads every record currently in the journal as `(page_id, pre-transaction bytes)` pairs.
+    fn journal_records(&self) -> Result<Vec<(usize, [u8; PAGE_SIZE])>, &'static str> {
+        let count = self.journal_record_count()?;
+        let mut records = Vec::with_capacity(count);
+        for i in 0..count {
+            let mut record = [0u8; JOURNAL_RECORD_SIZE];
+            self.journal
+                .read_exact_at(&mut record, Self::journal_record_offset(i))?;
+            let page_id = read_u32(&record, 0) as usize;
+            let mut bytes = [0u8; PAGE_SIZE];
+            bytes.copy_from_slice(&record[4..]);
+            records.push((page_id, bytes));
+        }
+        Ok(records)
+    }
+
+    /// Writes every journaled page's pre-transaction bytes back to the data
+    /// file, undoing whatever the unfinished transaction had done, then
+    /// truncates the journal.
+    fn replay_journal(&mut self) -> Result<(), &'static str> {
+        for (page_id, bytes) in self.journal_records()? {
+            self.file.write_all_at(&bytes, (page_id * PAGE_SIZE) as u64)?;
+        }
+        self.truncate_journal()
+    }
+
+    /// Flushes every loaded page to the backing store and fsyncs it, making
+    /// in-memory writes durable immediately rather than waiting for `Drop`.
+    fn sync(&mut self) -> Result<(), &'static str> {
+        for page_id in 0..self.pages.len() {
+            self.flush(page_id);
+        }
+        self.file.sync()
+    }
+
+    fn begin(&mut self) -> Result<(), &'static str> {
+        if self.in_transaction {
+            return Err("a transaction is already active");
+        }
+        self.in_transaction = true;
+        self.journaled_pages.clear();
+        self.truncate_journal()
+    }
+
+    fn commit(&mut self) -> Result<(), &'static str> {
+        if !self.in_transaction {
+            return Err("no transaction is active");
+        }
+        // the pre-images must be durable before any data page is overwritten
+        self.journal.sync()?;
+        self.sync()?;
+        self.truncate_journal()?;
+        self.in_transaction = false;
+        self.journaled_pages.clear();
+        Ok(())
+    }
+
+    /// Restores every page touched during the current transaction to its
+    /// pre-transaction contents and discards the buffered in-memory changes.
+    fn rollback(&mut self) -> Result<(), &'static str> {
+        if !self.in_transaction {
+            return Err("no transaction is active");
+        }
+        for (page_id, bytes) in self.journal_records()? {
+            self.file.write_all_at(&bytes, (page_id * PAGE_SIZE) as u64)?;
+            if page_id >= self.pages.len() {
+                self.pages.resize(page_id + 1, None);
+            }
+            self.pages[page_id] = Some(Page::new(bytes));
+        }
+        self.truncate_journal()?;
+        self.journaled_pages.clear();
+        self.in_transaction = false;
+        Ok(())
+    }
 }
 
 impl Drop for Pager {
@@ -104,82 +799,993 @@ impl Drop for Pager {
     }
 }
 
+/// Positions a scan over the leaves of a `Table`, following `next_leaf`
+/// pointers so the whole tree can be walked as an ordered sequence of rows.
+struct Cursor {
+    page_id: usize,
+    cell_index: usize,
+    end_of_table: bool,
+}
+
+impl Cursor {
+    fn at_start(table: &mut Table) -> Result<Self, &'static str> {
+        let page_id = table.leftmost_leaf()?;
+        let cell_count = table.pager.load_page(page_id)?.cell_count();
+        Ok(Self {
+            page_id,
+            cell_index: 0,
+            end_of_table: cell_count == 0,
+        })
+    }
+
+    /// Positions at the first cell whose key is `>= key`, whether or not
+    /// that key is actually present.
+    fn at_key(table: &mut Table, key: u32) -> Result<Self, &'static str> {
+        let (leaf_id, _path) = table.descend_to_leaf(key)?;
+        let row_size = table.row_size;
+        let page = table.pager.load_page(leaf_id)?;
+        let cell_index = leaf_lower_bound(page, key, row_size);
+        if cell_index < page.cell_count() {
+            return Ok(Self {
+                page_id: leaf_id,
+                cell_index,
+                end_of_table: false,
+            });
+        }
+        // every key in this leaf is smaller, so the first larger key (if
+        // any) starts the next leaf
+        let next = page.next_leaf();
+        Ok(Self {
+            page_id: if next == NO_PAGE { leaf_id } else { next as usize },
+            cell_index: if next == NO_PAGE { cell_index } else { 0 },
+            end_of_table: next == NO_PAGE,
+        })
+    }
+
+    fn advance(&mut self, table: &mut Table) -> Result<(), &'static str> {
+        if self.end_of_table {
+            return Ok(());
+        }
+
+        self.cell_index += 1;
+        let page = table.pager.load_page(self.page_id)?;
+        if self.cell_index >= page.cell_count() {
+            let next = page.next_leaf();
+            if next == NO_PAGE {
+                self.end_of_table = true;
+            } else {
+                self.page_id = next as usize;
+                self.cell_index = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn row(&self, table: &mut Table) -> Result<Row, &'static str> {
+        let row_size = table.row_size;
+        let page = table.pager.load_page(self.page_id)?;
+        bincode::deserialize(page.leaf_row_bytes(self.cell_index, row_size))
+            .map_err(|_| "failed to deserialize row")
+    }
+
+    fn key(&self, table: &mut Table) -> Result<u32, &'static str> {
+        let row_size = table.row_size;
+        let page = table.pager.load_page(self.page_id)?;
+        Ok(page.leaf_key(self.cell_index, row_size))
+    }
+}
+
+/// Trailer describing whether a page of results is the whole story, mirroring
+/// the shape of a GraphQL-style `PageInfo`.
+#[derive(Debug, PartialEq)]
+struct PageInfo {
+    has_next_page: bool,
+    next_cursor: Option<String>,
+}
+
+fn encode_cursor(id: u32) -> String {
+    BASE64.encode(id.to_le_bytes())
+}
+
+fn decode_cursor(cursor: &str) -> Result<u32, &'static str> {
+    let bytes = BASE64.decode(cursor).map_err(|_| "invalid cursor")?;
+    let bytes: [u8; 4] = bytes.as_slice().try_into().map_err(|_| "invalid cursor")?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Returns the index of the first cell in `page` whose key is `>= key`
+/// (i.e. `page.cell_count()` if every key is smaller).
+fn leaf_lower_bound(page: &Page, key: u32, row_size: usize) -> usize {
+    let mut lo = 0usize;
+    let mut hi = page.cell_count();
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if page.leaf_key(mid, row_size) < key {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// A column a secondary hash index can be built on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum IndexedColumn {
+    Username,
+    Email,
+}
+
+impl IndexedColumn {
+    fn parse(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "username" => Ok(Self::Username),
+            "email" => Ok(Self::Email),
+            _ => Err("unknown column"),
+        }
+    }
+
+    fn discriminant(self) -> u8 {
+        match self {
+            Self::Username => 0,
+            Self::Email => 1,
+        }
+    }
+
+    fn from_discriminant(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Username),
+            1 => Some(Self::Email),
+            _ => None,
+        }
+    }
+
+    fn value(self, row: &Row) -> &str {
+        match self {
+            Self::Username => row.username.trim(),
+            Self::Email => row.email.trim(),
+        }
+    }
+}
+
 struct Table {
     pager: Pager,
-    nb_rows: usize,
     row_size: usize,
 }
 
 impl Table {
-    fn new(pager: Pager) -> Self {
+    fn new(mut pager: Pager) -> Self {
         let row_size = bincode::serialized_size(&Row::new(0, "", "")).unwrap() as usize;
-        let rows_per_page = PAGE_SIZE / row_size;
 
-        // the assumption here is that every page expect the last one if full
-        // and that the last unfilled page will ocitian full rows
+        if !pager.is_initialized().unwrap_or(false) {
+            pager.bootstrap().unwrap();
+        }
 
-        let (nb_pages, rest) = pager.get_nb_pages();
-        let full_pages = max(nb_pages, 1) - 1;
-        let rows = full_pages * rows_per_page;
-        let nb_rows = (rows + rest) / row_size;
+        Self { pager, row_size }
+    }
 
-        Self {
-            nb_rows,
-            pager,
-            row_size,
-        }
+    fn in_transaction(&self) -> bool {
+        self.pager.in_transaction
     }
 
-    fn get_row_per_page(&self) -> usize {
-        PAGE_SIZE / self.row_size
+    fn begin(&mut self) -> Result<(), &'static str> {
+        self.pager.begin()
     }
 
-    fn get_page_id(&self, row_id: usize) -> usize {
-        row_id / self.get_row_per_page()
+    fn commit(&mut self) -> Result<(), &'static str> {
+        self.pager.commit()
     }
 
-    fn get_row_offset(&self, row_id: usize) -> usize {
-        (row_id % self.get_row_per_page()) * self.row_size
+    fn rollback(&mut self) -> Result<(), &'static str> {
+        self.pager.rollback()
     }
 
-    fn is_full(&self) -> bool {
-        let max = self.get_row_per_page() * TABLE_MAX_PAGES;
-        self.nb_rows == max
+    fn leaf_max_cells(&self) -> usize {
+        (PAGE_SIZE - NODE_HEADER_SIZE) / (4 + self.row_size)
+    }
+
+    fn internal_max_cells(&self) -> usize {
+        (PAGE_SIZE - NODE_HEADER_SIZE) / INTERNAL_CELL_SIZE
+    }
+
+    /// Descends from the root to the leaf that would hold `key`, returning
+    /// that leaf's page id and the chain of internal ancestors visited
+    /// (root-to-parent-of-leaf order) so splits can propagate back up.
+    fn descend_to_leaf(&mut self, key: u32) -> Result<(usize, Vec<usize>), &'static str> {
+        let mut page_id = self.pager.root_page_id()?;
+        let mut path = Vec::new();
+
+        loop {
+            let page = self.pager.load_page(page_id)?;
+            if page.is_leaf() {
+                return Ok((page_id, path));
+            }
+
+            let cell_count = page.cell_count();
+            let mut lo = 0usize;
+            let mut hi = cell_count;
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                if page.internal_key(mid) <= key {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            let child = if lo == cell_count {
+                page.right_child()
+            } else {
+                page.internal_child(lo)
+            };
+
+            path.push(page_id);
+            page_id = child as usize;
+        }
+    }
+
+    fn leftmost_leaf(&mut self) -> Result<usize, &'static str> {
+        let mut page_id = self.pager.root_page_id()?;
+        loop {
+            let page = self.pager.load_page(page_id)?;
+            if page.is_leaf() {
+                return Ok(page_id);
+            }
+            page_id = if page.cell_count() > 0 {
+                page.internal_child(0) as usize
+            } else {
+                page.right_child() as usize
+            };
+        }
     }
 
     fn insert_row(&mut self, row: &Row) -> Result<(), &'static str> {
-        if self.is_full() {
-            return Err("Table is full");
+        let key = row.id;
+        let row_bytes = bincode::serialize(row).map_err(|_| "failed to serialize row")?;
+        let row_size = self.row_size;
+
+        let (leaf_id, path) = self.descend_to_leaf(key)?;
+
+        let (cell_count, lo, exists) = {
+            let page = self.pager.load_page(leaf_id)?;
+            let cell_count = page.cell_count();
+            let lo = leaf_lower_bound(page, key, row_size);
+            (cell_count, lo, lo < cell_count && page.leaf_key(lo, row_size) == key)
+        };
+        if exists {
+            return Err("duplicate key");
+        }
+
+        if cell_count < self.leaf_max_cells() {
+            let page = self.pager.load_page(leaf_id)?;
+            for i in (lo..cell_count).rev() {
+                let key = page.leaf_key(i, row_size);
+                let bytes = page.leaf_row_bytes(i, row_size).to_vec();
+                page.set_leaf_cell(i + 1, key, &bytes, row_size);
+            }
+            page.set_leaf_cell(lo, key, &row_bytes, row_size);
+            page.set_cell_count(cell_count + 1);
+            self.refresh_zone_map(leaf_id)?;
+        } else {
+            self.split_leaf_with_new_cell(leaf_id, path, lo, key, row_bytes)?;
+        }
+
+        self.populate_indexes(row)
+    }
+
+    /// Recomputes the `[min, max]` zone map entry for `leaf_id` from its
+    /// current contents, dropping the entry entirely if the leaf is empty.
+    fn refresh_zone_map(&mut self, leaf_id: usize) -> Result<(), &'static str> {
+        let row_size = self.row_size;
+        let (min, max) = {
+            let page = self.pager.load_page(leaf_id)?;
+            let cell_count = page.cell_count();
+            if cell_count == 0 {
+                return self.pager.zone_map_remove(leaf_id);
+            }
+            (
+                page.leaf_key(0, row_size),
+                page.leaf_key(cell_count - 1, row_size),
+            )
+        };
+        self.pager.zone_map_set(leaf_id, min, max)
+    }
+
+    /// Inserting into a leaf that's already at capacity would overrun the
+    /// page, so the new cell is first merged into an in-memory copy of the
+    /// leaf's contents, which is then split in half across the old page and
+    /// a freshly allocated one, with the separator key propagated up.
+    fn split_leaf_with_new_cell(
+        &mut self,
+        leaf_id: usize,
+        path: Vec<usize>,
+        insert_at: usize,
+        key: u32,
+        row_bytes: Vec<u8>,
+    ) -> Result<(), &'static str> {
+        let row_size = self.row_size;
+        let (cell_count, next_leaf, parent) = {
+            let page = self.pager.load_page(leaf_id)?;
+            (page.cell_count(), page.next_leaf(), page.parent())
+        };
+
+        let mut cells = Vec::with_capacity(cell_count + 1);
+        {
+            let page = self.pager.load_page(leaf_id)?;
+            for i in 0..cell_count {
+                cells.push((
+                    page.leaf_key(i, row_size),
+                    page.leaf_row_bytes(i, row_size).to_vec(),
+                ));
+            }
         }
+        cells.insert(insert_at, (key, row_bytes));
 
-        match bincode::serialize(&row) {
-            Ok(row_bytes) => {
-                let page_id = self.get_page_id(row.id as usize);
-                let offset = self.get_row_offset(row.id as usize);
-                let page: &mut Page = self.pager.load_page(page_id)?;
+        let split_at = cells.len() / 2;
+        let new_page_id = self.pager.allocate_page()?;
 
-                match page.write(offset, &row_bytes) {
-                    Ok(_) => {
-                        self.nb_rows += 1;
-                        Ok(())
+        {
+            let left = self.pager.load_page(leaf_id)?;
+            for (i, (k, bytes)) in cells[..split_at].iter().enumerate() {
+                left.set_leaf_cell(i, *k, bytes, row_size);
+            }
+            left.set_cell_count(split_at);
+            left.set_next_leaf(new_page_id as u32);
+        }
+
+        {
+            let right = self.pager.load_page(new_page_id)?;
+            right.init_leaf();
+            right.set_parent(parent);
+            right.set_next_leaf(next_leaf);
+            for (i, (k, bytes)) in cells[split_at..].iter().enumerate() {
+                right.set_leaf_cell(i, *k, bytes, row_size);
+            }
+            right.set_cell_count(cells.len() - split_at);
+        }
+
+        let separator = cells[split_at].0;
+        self.refresh_zone_map(leaf_id)?;
+        self.refresh_zone_map(new_page_id)?;
+        self.propagate_split(leaf_id, separator, new_page_id, path)
+    }
+
+    /// Propagates a split up the tree: each ancestor gets the new
+    /// `(separator, right_id)` boundary inserted (creating a new root if the
+    /// old root itself split), splitting in turn if it was already full.
+    fn propagate_split(
+        &mut self,
+        mut left_id: usize,
+        mut separator: u32,
+        mut right_id: usize,
+        mut path: Vec<usize>,
+    ) -> Result<(), &'static str> {
+        loop {
+            match path.pop() {
+                None => return self.create_new_root(left_id, separator, right_id),
+                Some(parent_id) => {
+                    match self.insert_into_internal(parent_id, left_id, separator, right_id)? {
+                        None => return Ok(()),
+                        Some((next_separator, new_internal_id)) => {
+                            left_id = parent_id;
+                            separator = next_separator;
+                            right_id = new_internal_id;
+                        }
                     }
-                    Err(_) => Err("failed to copy to page"),
                 }
             }
-            Err(_) => Err("failed to serialize row"),
         }
     }
 
+    fn create_new_root(
+        &mut self,
+        left_id: usize,
+        separator: u32,
+        right_id: usize,
+    ) -> Result<(), &'static str> {
+        let new_root_id = self.pager.allocate_page()?;
+
+        {
+            let root = self.pager.load_page(new_root_id)?;
+            root.init_internal();
+            root.set_cell_count(1);
+            root.set_internal_cell(0, left_id as u32, separator);
+            root.set_right_child(right_id as u32);
+        }
+        self.pager.load_page(left_id)?.set_parent(new_root_id as u32);
+        self.pager.load_page(right_id)?.set_parent(new_root_id as u32);
+        self.pager.set_root_page_id(new_root_id)
+    }
+
+    /// Inserts a new `(separator, new_child_id)` boundary into `parent`,
+    /// replacing whichever reference pointed at `old_child_id` (either a
+    /// cell's child pointer or the rightmost-child pointer) since that
+    /// subtree was just split into `old_child_id` (smaller keys) and
+    /// `new_child_id` (larger keys). Returns `Some((separator, new_page_id))`
+    /// if `parent` was itself already full and had to split to make room.
+    fn insert_into_internal(
+        &mut self,
+        parent_id: usize,
+        old_child_id: usize,
+        separator: u32,
+        new_child_id: usize,
+    ) -> Result<Option<(u32, usize)>, &'static str> {
+        let (cell_count, right_child, parent) = {
+            let page = self.pager.load_page(parent_id)?;
+            (page.cell_count(), page.right_child(), page.parent())
+        };
+
+        let mut cells = Vec::with_capacity(cell_count);
+        {
+            let page = self.pager.load_page(parent_id)?;
+            for i in 0..cell_count {
+                cells.push((page.internal_child(i), page.internal_key(i)));
+            }
+        }
+
+        let (new_cells, new_right_child) = match cells
+            .iter()
+            .position(|&(child, _)| child as usize == old_child_id)
+        {
+            Some(idx) => {
+                let mut new_cells = Vec::with_capacity(cells.len() + 1);
+                new_cells.extend_from_slice(&cells[..idx]);
+                new_cells.push((old_child_id as u32, separator));
+                new_cells.push((new_child_id as u32, cells[idx].1));
+                new_cells.extend_from_slice(&cells[idx + 1..]);
+                (new_cells, right_child)
+            }
+            None => {
+                let mut new_cells = cells.clone();
+                new_cells.push((old_child_id as u32, separator));
+                (new_cells, new_child_id as u32)
+            }
+        };
+
+        self.pager
+            .load_page(new_child_id)?
+            .set_parent(parent_id as u32);
+
+        if new_cells.len() <= self.internal_max_cells() {
+            let page = self.pager.load_page(parent_id)?;
+            for (i, (child, key)) in new_cells.iter().enumerate() {
+                page.set_internal_cell(i, *child, *key);
+            }
+            page.set_cell_count(new_cells.len());
+            page.set_right_child(new_right_child);
+            return Ok(None);
+        }
+
+        // `parent` was already full: split it too, promoting the median key.
+        let mid = new_cells.len() / 2;
+        let median = new_cells[mid];
+        let new_page_id = self.pager.allocate_page()?;
+
+        {
+            let left = self.pager.load_page(parent_id)?;
+            for (i, (child, key)) in new_cells[..mid].iter().enumerate() {
+                left.set_internal_cell(i, *child, *key);
+            }
+            left.set_cell_count(mid);
+            left.set_right_child(median.0);
+        }
+
+        {
+            let right = self.pager.load_page(new_page_id)?;
+            right.init_internal();
+            right.set_parent(parent);
+            right.set_right_child(new_right_child);
+            for (i, (child, key)) in new_cells[mid + 1..].iter().enumerate() {
+                right.set_internal_cell(i, *child, *key);
+            }
+            right.set_cell_count(new_cells.len() - mid - 1);
+        }
+
+        let moved_children = new_cells[mid + 1..]
+            .iter()
+            .map(|(child, _)| *child)
+            .chain(std::iter::once(new_right_child));
+        for child_id in moved_children {
+            if child_id != NO_PAGE {
+                self.pager
+                    .load_page(child_id as usize)?
+                    .set_parent(new_page_id as u32);
+            }
+        }
+
+        Ok(Some((median.1, new_page_id)))
+    }
+
+    fn delete_row(&mut self, key: u32) -> Result<(), &'static str> {
+        let row_size = self.row_size;
+        let (leaf_id, path) = self.descend_to_leaf(key)?;
+
+        let (now_empty, row) = {
+            let page = self.pager.load_page(leaf_id)?;
+            let cell_count = page.cell_count();
+            let idx = leaf_lower_bound(page, key, row_size);
+            if idx >= cell_count || page.leaf_key(idx, row_size) != key {
+                return Err("key not found");
+            }
+            let row: Row = bincode::deserialize(page.leaf_row_bytes(idx, row_size))
+                .map_err(|_| "failed to deserialize row")?;
+            for i in idx + 1..cell_count {
+                let k = page.leaf_key(i, row_size);
+                let bytes = page.leaf_row_bytes(i, row_size).to_vec();
+                page.set_leaf_cell(i - 1, k, &bytes, row_size);
+            }
+            page.set_cell_count(cell_count - 1);
+            (cell_count - 1 == 0, row)
+        };
+
+        // the leaf was the root itself: leave it as an empty root rather
+        // than trying to free it away
+        if now_empty && !path.is_empty() {
+            self.unlink_and_free_leaf(leaf_id, path)?;
+        } else {
+            self.refresh_zone_map(leaf_id)?;
+        }
+
+        self.remove_from_indexes(&row)?;
+
+        Ok(())
+    }
+
+    /// Finds the leaf whose `next_leaf` points at `leaf_id`, if any (i.e.
+    /// `leaf_id` isn't the leftmost leaf).
+    fn find_predecessor_leaf(&mut self, leaf_id: usize) -> Result<Option<usize>, &'static str> {
+        let mut current = self.leftmost_leaf()?;
+        if current == leaf_id {
+            return Ok(None);
+        }
+        loop {
+            let next = self.pager.load_page(current)?.next_leaf();
+            if next as usize == leaf_id || next == NO_PAGE {
+                return Ok(if next as usize == leaf_id {
+                    Some(current)
+                } else {
+                    None
+                });
+            }
+            current = next as usize;
+        }
+    }
+
+    /// Removes a now-empty leaf from the ordered leaf chain and from its
+    /// parent, then returns its page to the free list.
+    fn unlink_and_free_leaf(
+        &mut self,
+        leaf_id: usize,
+        mut path: Vec<usize>,
+    ) -> Result<(), &'static str> {
+        let predecessor = self.find_predecessor_leaf(leaf_id)?;
+        let next_leaf = self.pager.load_page(leaf_id)?.next_leaf();
+        if let Some(pred_id) = predecessor {
+            self.pager.load_page(pred_id)?.set_next_leaf(next_leaf);
+        }
+
+        let parent_id = path.pop().expect("a non-root leaf has a parent");
+        self.remove_child_from_internal(parent_id, leaf_id)?;
+        self.pager.zone_map_remove(leaf_id)?;
+        self.pager.free_page(leaf_id)
+    }
+
+    /// Removes the reference to `child_id` from `parent` (whether it was a
+    /// cell's child pointer or the rightmost-child pointer) now that the
+    /// subtree under it is gone. If that leaves the root with no cells and a
+    /// single remaining child, that child is promoted to be the new root so
+    /// the tree doesn't carry a useless level of indirection.
+    fn remove_child_from_internal(
+        &mut self,
+        parent_id: usize,
+        child_id: usize,
+    ) -> Result<(), &'static str> {
+        let (cell_count, right_child, grandparent) = {
+            let page = self.pager.load_page(parent_id)?;
+            (page.cell_count(), page.right_child(), page.parent())
+        };
+
+        if right_child as usize == child_id && cell_count == 0 {
+            // `parent` just lost its only child, so it is empty and goes too
+            self.remove_child_from_internal(grandparent as usize, parent_id)?;
+            return self.pager.free_page(parent_id);
+        }
+
+        if right_child as usize == child_id {
+            let last_child = self
+                .pager
+                .load_page(parent_id)?
+                .internal_child(cell_count - 1);
+            let page = self.pager.load_page(parent_id)?;
+            page.set_cell_count(cell_count - 1);
+            page.set_right_child(last_child);
+        } else {
+            let page = self.pager.load_page(parent_id)?;
+            let idx = (0..cell_count)
+                .find(|&i| page.internal_child(i) as usize == child_id)
+                .ok_or("child not found in parent")?;
+            for i in idx + 1..cell_count {
+                let (child, key) = (page.internal_child(i), page.internal_key(i));
+                page.set_internal_cell(i - 1, child, key);
+            }
+            page.set_cell_count(cell_count - 1);
+        }
+
+        if grandparent == NO_PAGE {
+            let (cell_count, right_child) = {
+                let page = self.pager.load_page(parent_id)?;
+                (page.cell_count(), page.right_child())
+            };
+            if cell_count == 0 {
+                self.pager
+                    .load_page(right_child as usize)?
+                    .set_parent(NO_PAGE);
+                self.pager.set_root_page_id(right_child as usize)?;
+                self.pager.free_page(parent_id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ordered scan of every row in the table, following leaf `next_leaf`
+    /// pointers rather than a dense `id`-indexed array.
     fn select_row(&mut self) -> Vec<Row> {
-        (0..self.nb_rows)
-            .map(|row_id| {
-                let page_id = self.get_page_id(row_id);
-                let row_offset = self.get_row_offset(row_id);
-                let page = self.pager.load_page(page_id).unwrap();
-                let slice = &page.bytes[row_offset..row_offset + self.row_size];
-                bincode::deserialize(slice).unwrap()
-            })
-            .collect()
+        let mut rows = Vec::new();
+        let mut cursor = match Cursor::at_start(self) {
+            Ok(cursor) => cursor,
+            Err(_) => return rows,
+        };
+
+        while !cursor.end_of_table {
+            match cursor.row(self) {
+                Ok(row) => rows.push(row),
+                Err(_) => break,
+            }
+            if cursor.advance(self).is_err() {
+                break;
+            }
+        }
+
+        rows
+    }
+
+    /// Keyset-paginated scan: returns at most `first` rows whose `id` is
+    /// strictly greater than `after` (or from the start of the table if
+    /// `after` is `None`), plus a `PageInfo` trailer for walking the next
+    /// page.
+    fn select_page(
+        &mut self,
+        first: usize,
+        after: Option<u32>,
+    ) -> Result<(Vec<Row>, PageInfo), &'static str> {
+        if first > MAX_PAGE_SIZE {
+            return Err("requested page size exceeds maximum allowed");
+        }
+
+        let mut cursor = match after {
+            Some(key) => Cursor::at_key(self, key)?,
+            None => Cursor::at_start(self)?,
+        };
+        if let Some(key) = after {
+            if !cursor.end_of_table && cursor.key(self)? == key {
+                cursor.advance(self)?;
+            }
+        }
+
+        let mut rows = Vec::with_capacity(first);
+        while !cursor.end_of_table && rows.len() < first {
+            rows.push(cursor.row(self)?);
+            cursor.advance(self)?;
+        }
+
+        let page_info = PageInfo {
+            has_next_page: !cursor.end_of_table,
+            next_cursor: rows.last().map(|row| encode_cursor(row.id)),
+        };
+        Ok((rows, page_info))
+    }
+
+    /// Returns every row with `lo <= id <= hi`. When the zone map covers
+    /// every leaf, the scan is driven directly off its (sorted) entries, so
+    /// a leaf whose `[min, max]` interval can't overlap the range is never
+    /// loaded at all. If the map has ever overflowed its fixed-size slot on
+    /// page 0, that guarantee is unavailable for the leaves it dropped, so
+    /// the scan instead walks the full leaf chain, still using whatever
+    /// entries remain as a best-effort skip hint, to make sure no rows are
+    /// lost. Also reports how many leaf pages were skipped this way.
+    fn select_where_between(
+        &mut self,
+        lo: u32,
+        hi: u32,
+    ) -> Result<(Vec<Row>, usize), &'static str> {
+        let row_size = self.row_size;
+        let mut entries = self.pager.zone_map_entries()?;
+        entries.sort_by_key(|&(_, min, _)| min);
+
+        let mut rows = Vec::new();
+        let mut skipped = 0;
+
+        if self.pager.zone_map_overflowed()? {
+            let bounds: HashMap<usize, (u32, u32)> = entries
+                .into_iter()
+                .map(|(page_id, min, max)| (page_id, (min, max)))
+                .collect();
+            let mut page_id = self.leftmost_leaf()?;
+            loop {
+                let can_skip =
+                    matches!(bounds.get(&page_id), Some(&(min, max)) if max < lo || min > hi);
+                let next_leaf = if can_skip {
+                    skipped += 1;
+                    self.pager.load_page(page_id)?.next_leaf()
+                } else {
+                    let page = self.pager.load_page(page_id)?;
+                    for i in 0..page.cell_count() {
+                        let key = page.leaf_key(i, row_size);
+                        if key >= lo && key <= hi {
+                            let row = bincode::deserialize(page.leaf_row_bytes(i, row_size))
+                                .map_err(|_| "failed to deserialize row")?;
+                            rows.push(row);
+                        }
+                    }
+                    page.next_leaf()
+                };
+                if next_leaf == NO_PAGE {
+                    break;
+                }
+                page_id = next_leaf as usize;
+            }
+        } else {
+            for (page_id, min, max) in entries {
+                if max < lo || min > hi {
+                    skipped += 1;
+                    continue;
+                }
+                let page = self.pager.load_page(page_id)?;
+                for i in 0..page.cell_count() {
+                    let key = page.leaf_key(i, row_size);
+                    if key >= lo && key <= hi {
+                        let row = bincode::deserialize(page.leaf_row_bytes(i, row_size))
+                            .map_err(|_| "failed to deserialize row")?;
+                        rows.push(row);
+                    }
+                }
+            }
+        }
+
+        rows.sort_by_key(|row: &Row| row.id);
+        Ok((rows, skipped))
+    }
+
+    fn find_row_by_id(&mut self, id: u32) -> Result<Option<Row>, &'static str> {
+        let row_size = self.row_size;
+        let (leaf_id, _path) = self.descend_to_leaf(id)?;
+        let page = self.pager.load_page(leaf_id)?;
+        let idx = leaf_lower_bound(page, id, row_size);
+        if idx >= page.cell_count() || page.leaf_key(idx, row_size) != id {
+            return Ok(None);
+        }
+        let row = bincode::deserialize(page.leaf_row_bytes(idx, row_size))
+            .map_err(|_| "failed to deserialize row")?;
+        Ok(Some(row))
+    }
+
+    fn index_catalog_entries(
+        &mut self,
+    ) -> Result<Vec<(IndexedColumn, [u32; INDEX_BUCKET_COUNT])>, &'static str> {
+        let catalog_id = self.pager.index_catalog_page_id()?;
+        if catalog_id == NO_PAGE {
+            return Ok(Vec::new());
+        }
+
+        let page = self.pager.load_page(catalog_id as usize)?;
+        let count = page.bytes[INDEX_CATALOG_COUNT_OFFSET] as usize;
+        let mut entries = Vec::with_capacity(count);
+        for i in 0..count {
+            let offset = INDEX_CATALOG_ENTRIES_OFFSET + i * INDEX_CATALOG_ENTRY_SIZE;
+            let column = IndexedColumn::from_discriminant(page.bytes[offset])
+                .ok_or("corrupt index catalog")?;
+            let mut bucket_pages = [0u32; INDEX_BUCKET_COUNT];
+            for (b, slot) in bucket_pages.iter_mut().enumerate() {
+                *slot = read_u32(&page.bytes, offset + 1 + b * 4);
+            }
+            entries.push((column, bucket_pages));
+        }
+        Ok(entries)
+    }
+
+    fn append_index_catalog_entry(
+        &mut self,
+        column: IndexedColumn,
+        bucket_pages: &[u32; INDEX_BUCKET_COUNT],
+    ) -> Result<(), &'static str> {
+        let catalog_id = match self.pager.index_catalog_page_id()? {
+            NO_PAGE => {
+                let id = self.pager.allocate_page()?;
+                self.pager.load_page(id)?.bytes[INDEX_CATALOG_COUNT_OFFSET] = 0;
+                self.pager.set_index_catalog_page_id(id as u32)?;
+                id
+            }
+            id => id as usize,
+        };
+
+        let page = self.pager.load_page(catalog_id)?;
+        let count = page.bytes[INDEX_CATALOG_COUNT_OFFSET] as usize;
+        if count >= INDEX_CATALOG_MAX_ENTRIES {
+            return Err("index catalog is full");
+        }
+        let offset = INDEX_CATALOG_ENTRIES_OFFSET + count * INDEX_CATALOG_ENTRY_SIZE;
+        page.bytes[offset] = column.discriminant();
+        for (b, &page_id) in bucket_pages.iter().enumerate() {
+            write_u32(&mut page.bytes, offset + 1 + b * 4, page_id);
+        }
+        page.bytes[INDEX_CATALOG_COUNT_OFFSET] = (count + 1) as u8;
+        Ok(())
+    }
+
+    /// Reserves `INDEX_BUCKET_COUNT` bucket pages for a new index on
+    /// `column` and backfills it from every row already in the table.
+    fn create_index(&mut self, column: IndexedColumn) -> Result<(), &'static str> {
+        if self
+            .index_catalog_entries()?
+            .iter()
+            .any(|&(existing, _)| existing == column)
+        {
+            return Err("index already exists");
+        }
+
+        let mut bucket_pages = [0u32; INDEX_BUCKET_COUNT];
+        for slot in bucket_pages.iter_mut() {
+            let page_id = self.pager.allocate_page()?;
+            self.pager.load_page(page_id)?.init_index_page();
+            *slot = page_id as u32;
+        }
+        self.append_index_catalog_entry(column, &bucket_pages)?;
+
+        for row in self.select_row() {
+            self.index_insert(column, &bucket_pages, &row)?;
+        }
+        Ok(())
+    }
+
+    /// Inserts `row` into every live index; called once a row has been
+    /// written to the B+tree.
+    fn populate_indexes(&mut self, row: &Row) -> Result<(), &'static str> {
+        for (column, bucket_pages) in self.index_catalog_entries()? {
+            self.index_insert(column, &bucket_pages, row)?;
+        }
+        Ok(())
+    }
+
+    /// Removes `row`'s slot from every live index; called once a row has
+    /// been removed from the B+tree so indexes don't accumulate stale slots.
+    fn remove_from_indexes(&mut self, row: &Row) -> Result<(), &'static str> {
+        for (column, bucket_pages) in self.index_catalog_entries()? {
+            self.index_delete(column, &bucket_pages, row)?;
+        }
+        Ok(())
+    }
+
+    /// Hashes `column`'s value in `row` and appends a `(hash, row id)` slot
+    /// to the right bucket, chaining to a fresh overflow page if the bucket
+    /// (and every page already chained off it) is full.
+    fn index_insert(
+        &mut self,
+        column: IndexedColumn,
+        bucket_pages: &[u32; INDEX_BUCKET_COUNT],
+        row: &Row,
+    ) -> Result<(), &'static str> {
+        let hash = murmur3_32(column.value(row).as_bytes(), 0);
+        let mut page_id = bucket_pages[hash as usize % INDEX_BUCKET_COUNT] as usize;
+
+        loop {
+            let (slot_count, overflow) = {
+                let page = self.pager.load_page(page_id)?;
+                (page.index_slot_count(), page.index_overflow())
+            };
+
+            if slot_count < index_max_slots() {
+                let page = self.pager.load_page(page_id)?;
+                page.set_index_slot(slot_count, hash, row.id);
+                page.set_index_slot_count(slot_count + 1);
+                return Ok(());
+            }
+
+            if overflow != NO_PAGE {
+                page_id = overflow as usize;
+                continue;
+            }
+
+            let new_page_id = self.pager.allocate_page()?;
+            self.pager.load_page(new_page_id)?.init_index_page();
+            self.pager
+                .load_page(page_id)?
+                .set_index_overflow(new_page_id as u32);
+            page_id = new_page_id;
+        }
+    }
+
+    /// Removes `row`'s `(hash, row id)` slot from its bucket's overflow
+    /// chain, compacting the page it was found on by moving the last slot
+    /// into the freed spot.
+    fn index_delete(
+        &mut self,
+        column: IndexedColumn,
+        bucket_pages: &[u32; INDEX_BUCKET_COUNT],
+        row: &Row,
+    ) -> Result<(), &'static str> {
+        let hash = murmur3_32(column.value(row).as_bytes(), 0);
+        let mut page_id = bucket_pages[hash as usize % INDEX_BUCKET_COUNT] as usize;
+
+        loop {
+            let (slot_count, overflow) = {
+                let page = self.pager.load_page(page_id)?;
+                (page.index_slot_count(), page.index_overflow())
+            };
+
+            let page = self.pager.load_page(page_id)?;
+            if let Some(i) = (0..slot_count).find(|&i| page.index_slot(i) == (hash, row.id)) {
+                let last = slot_count - 1;
+                if i != last {
+                    let (last_hash, last_row_id) = page.index_slot(last);
+                    page.set_index_slot(i, last_hash, last_row_id);
+                }
+                page.set_index_slot_count(last);
+                return Ok(());
+            }
+
+            if overflow == NO_PAGE {
+                return Ok(());
+            }
+            page_id = overflow as usize;
+        }
+    }
+
+    /// Looks up rows whose `column` equals `value` via its secondary index:
+    /// hashes the value, walks the matching bucket's overflow chain for
+    /// slots with that hash, then fetches and re-checks each candidate row
+    /// (hashes can collide across different values).
+    fn select_where_column_equals(
+        &mut self,
+        column: IndexedColumn,
+        value: &str,
+    ) -> Result<Vec<Row>, &'static str> {
+        let bucket_pages = self
+            .index_catalog_entries()?
+            .into_iter()
+            .find(|&(existing, _)| existing == column)
+            .map(|(_, bucket_pages)| bucket_pages)
+            .ok_or("no index on this column")?;
+
+        let hash = murmur3_32(value.as_bytes(), 0);
+        let mut page_id = bucket_pages[hash as usize % INDEX_BUCKET_COUNT] as usize;
+
+        let mut row_ids = Vec::new();
+        loop {
+            let (slot_count, overflow) = {
+                let page = self.pager.load_page(page_id)?;
+                (page.index_slot_count(), page.index_overflow())
+            };
+            for i in 0..slot_count {
+                let (slot_hash, row_id) = self.pager.load_page(page_id)?.index_slot(i);
+                if slot_hash == hash {
+                    row_ids.push(row_id);
+                }
+            }
+            if overflow == NO_PAGE {
+                break;
+            }
+            page_id = overflow as usize;
+        }
+
+        let mut rows = Vec::new();
+        for id in row_ids {
+            if let Some(row) = self.find_row_by_id(id)? {
+                if column.value(&row) == value {
+                    rows.push(row);
+                }
+            }
+        }
+        Ok(rows)
     }
 }
 
@@ -221,20 +1827,84 @@ fn pad_string(input: &str, size: usize) -> String {
     s
 }
 
-#[derive(Debug, PartialEq)]
-enum Statement {
-    Select,
-    Insert(Row),
+#[derive(Debug, PartialEq)]
+enum Statement {
+    Select,
+    SelectPage { first: usize, after: Option<u32> },
+    SelectWhere { lo: u32, hi: u32 },
+    SelectWhereColumn { column: IndexedColumn, value: String },
+    Insert(Row),
+    Delete(u32),
+    CreateIndex(IndexedColumn),
+}
+
+fn parse_insert(words: &[&str]) -> Result<Statement, &'static str> {
+    match words {
+        // should new do the validation or should it be done before ?
+        [id, username, email] => match id.parse() {
+            Ok(id) => Ok(Statement::Insert(Row::new(id, username, email))),
+            _ => Err("invalid id. not a number"),
+        },
+        _ => Err("invalid insert expected 3 args"),
+    }
+}
+
+fn parse_delete(words: &[&str]) -> Result<Statement, &'static str> {
+    match words {
+        [id] => match id.parse() {
+            Ok(id) => Ok(Statement::Delete(id)),
+            _ => Err("invalid id. not a number"),
+        },
+        _ => Err("invalid delete expected 1 arg"),
+    }
+}
+
+/// Strips a single pair of surrounding single quotes, if present, e.g. to
+/// let `select where username = 'alice'` match the request's SQL-ish
+/// syntax. There's no support for quoted strings containing spaces: values
+/// are still split on whitespace like every other statement in this parser.
+fn strip_quotes(value: &str) -> &str {
+    value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .unwrap_or(value)
+}
+
+fn parse_select(words: &[&str]) -> Result<Statement, &'static str> {
+    match words {
+        [] => Ok(Statement::Select),
+        ["first", n] => {
+            let first = n.parse().map_err(|_| "invalid page size. not a number")?;
+            Ok(Statement::SelectPage { first, after: None })
+        }
+        ["first", n, "after", cursor] => {
+            let first = n.parse().map_err(|_| "invalid page size. not a number")?;
+            let after = Some(decode_cursor(cursor)?);
+            Ok(Statement::SelectPage { first, after })
+        }
+        ["where", "id", "between", lo, "and", hi] => {
+            let lo = lo.parse().map_err(|_| "invalid id. not a number")?;
+            let hi = hi.parse().map_err(|_| "invalid id. not a number")?;
+            Ok(Statement::SelectWhere { lo, hi })
+        }
+        ["where", "id", "=", id] => {
+            let id = id.parse().map_err(|_| "invalid id. not a number")?;
+            Ok(Statement::SelectWhere { lo: id, hi: id })
+        }
+        ["where", column, "=", value] if IndexedColumn::parse(column).is_ok() => {
+            Ok(Statement::SelectWhereColumn {
+                column: IndexedColumn::parse(column)?,
+                value: strip_quotes(value).to_string(),
+            })
+        }
+        _ => Err("invalid select"),
+    }
 }
 
-fn parse_insert(words: &[&str]) -> Result<Statement, &'static str> {
+fn parse_create(words: &[&str]) -> Result<Statement, &'static str> {
     match words {
-        // should new do the validation or should it be done before ?
-        [id, username, email] => match id.parse() {
-            Ok(id) => Ok(Statement::Insert(Row::new(id, username, email))),
-            _ => Err("invalid id. not a number"),
-        },
-        _ => Err("invalid insert expected 3 args"),
+        ["index", "on", column] => Ok(Statement::CreateIndex(IndexedColumn::parse(column)?)),
+        _ => Err("invalid create"),
     }
 }
 
@@ -245,6 +1915,10 @@ fn execute_statment(statement: Statement, table: &mut Table) -> Result<String, &
             table.insert_row(&row)?;
             Ok(out)
         }
+        Statement::Delete(id) => {
+            table.delete_row(id)?;
+            Ok(String::new())
+        }
         Statement::Select => {
             let mut out = String::new();
             for row in table.select_row() {
@@ -252,6 +1926,55 @@ fn execute_statment(statement: Statement, table: &mut Table) -> Result<String, &
             }
             Ok(out)
         }
+        Statement::SelectPage { first, after } => {
+            let (rows, page_info) = table.select_page(first, after)?;
+            let mut out = String::new();
+            for row in &rows {
+                out += format!("{:?}\n", row).as_str();
+            }
+            out += format!(
+                "-- {} row(s), has_next_page={}, next_cursor={}\n",
+                rows.len(),
+                page_info.has_next_page,
+                page_info.next_cursor.as_deref().unwrap_or("none")
+            )
+            .as_str();
+            Ok(out)
+        }
+        Statement::SelectWhere { lo, hi } if lo == hi => {
+            // `id = k` is just `between k and k`, but routing it through the
+            // B+tree point lookup instead of a zone-map leaf scan keeps it
+            // the O(log n) lookup chunk0-1 built `find_row_by_id` for.
+            let row = table.find_row_by_id(lo)?;
+            let mut out = String::new();
+            if let Some(row) = &row {
+                out += format!("{:?}\n", row).as_str();
+            }
+            out += format!("-- {} row(s)\n", row.is_some() as usize).as_str();
+            Ok(out)
+        }
+        Statement::SelectWhere { lo, hi } => {
+            let (rows, skipped) = table.select_where_between(lo, hi)?;
+            let mut out = String::new();
+            for row in &rows {
+                out += format!("{:?}\n", row).as_str();
+            }
+            out += format!("-- {} row(s), {} page(s) skipped\n", rows.len(), skipped).as_str();
+            Ok(out)
+        }
+        Statement::SelectWhereColumn { column, value } => {
+            let rows = table.select_where_column_equals(column, &value)?;
+            let mut out = String::new();
+            for row in &rows {
+                out += format!("{:?}\n", row).as_str();
+            }
+            out += format!("-- {} row(s)\n", rows.len()).as_str();
+            Ok(out)
+        }
+        Statement::CreateIndex(column) => {
+            table.create_index(column)?;
+            Ok(String::new())
+        }
     }
 }
 
@@ -260,7 +1983,9 @@ fn parse_statement(line: String) -> Result<Statement, &'static str> {
 
     match parts.as_slice() {
         ["insert", rest @ ..] => parse_insert(rest),
-        ["select"] => Ok(Statement::Select),
+        ["select", rest @ ..] => parse_select(rest),
+        ["delete", rest @ ..] => parse_delete(rest),
+        ["create", rest @ ..] => parse_create(rest),
         _ => Err("unknown command"),
     }
 }
@@ -272,14 +1997,26 @@ fn parse_statement(line: String) -> Result<Statement, &'static str> {
 enum Command {
     Help,
     Exit,
+    Begin,
+    Commit,
+    Rollback,
 }
 const EXIT_COMMAND: &str = ".exit";
 const HELP_COMMAND: &str = ".help";
+const BEGIN_COMMAND: &str = ".begin";
+const COMMIT_COMMAND: &str = ".commit";
+const ROLLBACK_COMMAND: &str = ".rollback";
 
-fn execute_command(command: Command) {
+fn execute_command(command: Command, table: &mut Table) -> Result<(), &'static str> {
     match command {
-        Command::Help => print_help(),
+        Command::Help => {
+            print_help();
+            Ok(())
+        }
         Command::Exit => exit(),
+        Command::Begin => table.begin(),
+        Command::Commit => table.commit(),
+        Command::Rollback => table.rollback(),
     }
 }
 
@@ -287,7 +2024,7 @@ fn print_help() {
     println!("help");
 }
 
-fn exit() {
+fn exit() -> ! {
     std::process::exit(0)
 }
 
@@ -295,6 +2032,9 @@ fn parse_command(line: String) -> Result<Command, &'static str> {
     match line.trim() {
         EXIT_COMMAND => Ok(Command::Exit),
         HELP_COMMAND => Ok(Command::Help),
+        BEGIN_COMMAND => Ok(Command::Begin),
+        COMMIT_COMMAND => Ok(Command::Commit),
+        ROLLBACK_COMMAND => Ok(Command::Rollback),
         _ => Err("unknown command"),
     }
 }
@@ -318,7 +2058,14 @@ fn main() -> rustyline::Result<()> {
         .truncate(false)
         .open(filename)
         .unwrap();
-    let pager = Pager::new(Box::new(file));
+    let journal = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open("c.db.journal")
+        .unwrap();
+    let pager = Pager::new(Box::new(file), Box::new(journal));
     let mut table = Table::new(pager);
 
     loop {
@@ -331,14 +2078,39 @@ fn main() -> rustyline::Result<()> {
                 }
                 match line.chars().next() {
                     Some('.') => match parse_command(line) {
-                        Ok(command) => execute_command(command),
+                        Ok(command) => {
+                            if let Err(err) = execute_command(command, &mut table) {
+                                println!("Error: {}", err);
+                            }
+                        }
                         Err(err) => println!("Error: {}", err),
                     },
+                    // Each statement runs in an implicit transaction when
+                    // none is already active, so a single insert/delete is
+                    // crash-safe even without an explicit `.begin`/`.commit`.
                     Some(_) => match parse_statement(line) {
-                        Ok(statement) => match execute_statment(statement, &mut table) {
-                            Ok(out) => println!("{}", out),
-                            Err(err) => println!("Error: {}", err),
-                        },
+                        Ok(statement) => {
+                            let implicit = !table.in_transaction();
+                            let result = if implicit {
+                                table.begin().and_then(|_| execute_statment(statement, &mut table))
+                            } else {
+                                execute_statment(statement, &mut table)
+                            };
+                            match result {
+                                Ok(out) => {
+                                    if implicit {
+                                        let _ = table.commit();
+                                    }
+                                    println!("{}", out);
+                                }
+                                Err(err) => {
+                                    if implicit {
+                                        let _ = table.rollback();
+                                    }
+                                    println!("Error: {}", err);
+                                }
+                            }
+                        }
                         Err(err) => println!("Error: {}", err),
                     },
                     None => {
@@ -371,10 +2143,29 @@ mod tests {
     use super::*;
     use tempfile::tempfile;
 
+    fn test_table() -> Table {
+        let data: Vec<u8> = vec![0x0; PAGE_SIZE];
+        let cursor = Box::new(std::io::Cursor::new(data)) as Box<dyn RW>;
+        let journal = Box::new(std::io::Cursor::new(Vec::new())) as Box<dyn RW>;
+        Table::new(Pager::new(cursor, journal))
+    }
+
     #[test]
     fn commands() {
         assert_eq!(parse_command(String::from(".help")).unwrap(), Command::Help);
         assert_eq!(parse_command(String::from(".exit")).unwrap(), Command::Exit);
+        assert_eq!(
+            parse_command(String::from(".begin")).unwrap(),
+            Command::Begin
+        );
+        assert_eq!(
+            parse_command(String::from(".commit")).unwrap(),
+            Command::Commit
+        );
+        assert_eq!(
+            parse_command(String::from(".rollback")).unwrap(),
+            Command::Rollback
+        );
         assert_eq!(
             parse_command(String::from(".elxit")),
             Err("unknown command")
@@ -389,6 +2180,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn statement_select_first() {
+        assert_eq!(
+            parse_statement(String::from("select first 10")).unwrap(),
+            Statement::SelectPage {
+                first: 10,
+                after: None
+            }
+        );
+        assert_eq!(
+            parse_statement(format!("select first 10 after {}", encode_cursor(3))).unwrap(),
+            Statement::SelectPage {
+                first: 10,
+                after: Some(3)
+            }
+        );
+        assert_eq!(
+            parse_statement(String::from("select first ten")),
+            Err("invalid page size. not a number")
+        );
+        assert_eq!(
+            parse_statement(String::from("select first 10 after not-base64!")),
+            Err("invalid cursor")
+        );
+    }
+
+    #[test]
+    fn statement_select_where() {
+        assert_eq!(
+            parse_statement(String::from("select where id between 2 and 5")).unwrap(),
+            Statement::SelectWhere { lo: 2, hi: 5 }
+        );
+        assert_eq!(
+            parse_statement(String::from("select where id = 3")).unwrap(),
+            Statement::SelectWhere { lo: 3, hi: 3 }
+        );
+        assert_eq!(
+            parse_statement(String::from("select where id between a and 5")),
+            Err("invalid id. not a number")
+        );
+        assert_eq!(
+            parse_statement(String::from("select where username = 'alice'")).unwrap(),
+            Statement::SelectWhereColumn {
+                column: IndexedColumn::Username,
+                value: String::from("alice"),
+            }
+        );
+        assert_eq!(
+            parse_statement(String::from("select where email = bob@example.com")).unwrap(),
+            Statement::SelectWhereColumn {
+                column: IndexedColumn::Email,
+                value: String::from("bob@example.com"),
+            }
+        );
+    }
+
+    #[test]
+    fn statement_create_index() {
+        assert_eq!(
+            parse_statement(String::from("create index on username")).unwrap(),
+            Statement::CreateIndex(IndexedColumn::Username)
+        );
+        assert_eq!(
+            parse_statement(String::from("create index on favorite_color")),
+            Err("unknown column")
+        );
+        assert_eq!(
+            parse_statement(String::from("create table users")),
+            Err("invalid create")
+        );
+    }
+
     #[test]
     fn statement_insert() {
         assert_eq!(
@@ -405,6 +2268,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn statement_delete() {
+        assert_eq!(
+            parse_statement(String::from("delete 1")).unwrap(),
+            Statement::Delete(1)
+        );
+        assert_eq!(
+            parse_statement(String::from("delete")),
+            Err("invalid delete expected 1 arg")
+        );
+        assert_eq!(
+            parse_statement(String::from("delete a")),
+            Err("invalid id. not a number")
+        );
+    }
+
     #[test]
     fn insert_truncate() {
         let username = "0123456789123456789012345678901234".to_string();
@@ -428,38 +2307,24 @@ mod tests {
         assert_eq!(row.email, pad_string("bar", COLUMN_EMAIL_SIZE));
     }
 
-    #[test]
-    fn page() {
-        let mut page = Page::new([0x0; PAGE_SIZE]);
-        assert_eq!(
-            page.write(PAGE_SIZE, &[0x1]),
-            Err("not enough space to write")
-        );
-
-        assert_eq!(page.bytes.len(), PAGE_SIZE);
-        assert_eq!(page.end_offset, 0);
-        assert_eq!(page.write(100, &[0x1; 10]), Ok(()));
-        assert_eq!(page.end_offset, 110);
-    }
-
     #[test]
     fn pager() {
         let data: Vec<u8> = vec![0x0; PAGE_SIZE];
         let cursor = Box::new(std::io::Cursor::new(data)) as Box<dyn RW>;
-        let pager = Pager::new(Box::new(cursor));
+        let journal = Box::new(std::io::Cursor::new(Vec::new())) as Box<dyn RW>;
+        let pager = Pager::new(cursor, journal);
         assert_eq!(pager.get_nb_pages(), (1, 0));
 
         let data: Vec<u8> = vec![0x0; PAGE_SIZE + 7];
         let cursor = Box::new(std::io::Cursor::new(data)) as Box<dyn RW>;
-        let pager = Pager::new(Box::new(cursor));
+        let journal = Box::new(std::io::Cursor::new(Vec::new())) as Box<dyn RW>;
+        let pager = Pager::new(cursor, journal);
         assert_eq!(pager.get_nb_pages(), (2, 7));
     }
 
     #[test]
     fn table() {
-        let data: Vec<u8> = vec![0x0; PAGE_SIZE];
-        let cursor = Box::new(std::io::Cursor::new(data)) as Box<dyn RW>;
-        let mut table = Table::new(Pager::new(cursor));
+        let mut table = test_table();
         let _ = table.insert_row(&Row::new(1, "foo", "bar"));
         let _ = table.insert_row(&Row::new(2, "foo", "bar"));
         let rows = table.select_row();
@@ -467,26 +2332,303 @@ mod tests {
     }
 
     #[test]
-    fn fill_table() {
-        let data: Vec<u8> = vec![0x0; PAGE_SIZE];
-        let cursor = Box::new(std::io::Cursor::new(data)) as Box<dyn RW>;
-        let mut table = Table::new(Pager::new(cursor));
-        let max = (table.get_row_per_page() * TABLE_MAX_PAGES) as u32;
-        for i in 0..max {
-            let res = table.insert_row(&Row::new(i, "foo", "bar"));
-            assert!(res.is_ok());
+    fn select_is_ordered_by_key_regardless_of_insertion_order() {
+        let mut table = test_table();
+
+        for id in [5u32, 1, 4, 2, 3] {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+
+        let rows = table.select_row();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn insert_duplicate_key_is_rejected() {
+        let mut table = test_table();
+
+        table.insert_row(&Row::new(1, "foo", "bar")).unwrap();
+        assert_eq!(
+            table.insert_row(&Row::new(1, "foo", "baz")),
+            Err("duplicate key")
+        );
+    }
+
+    #[test]
+    fn insert_many_rows_splits_the_tree() {
+        let mut table = test_table();
+
+        let n = 2000u32;
+        // insert out of order so the tree has to split both leaves and
+        // internal nodes, not just append at the tail
+        for id in (0..n).rev() {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+
+        let rows = table.select_row();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        let expected: Vec<u32> = (0..n).collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn delete_row_removes_it_from_subsequent_selects() {
+        let mut table = test_table();
+
+        for id in 1u32..=3 {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+        table.delete_row(2).unwrap();
+
+        let rows = table.select_row();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn delete_row_rejects_a_missing_key() {
+        let mut table = test_table();
+
+        table.insert_row(&Row::new(1, "foo", "bar")).unwrap();
+        assert_eq!(table.delete_row(2), Err("key not found"));
+    }
+
+    #[test]
+    fn freed_leaf_pages_are_reused_by_later_inserts() {
+        let mut table = test_table();
+
+        let n = 2000u32;
+        for id in 0..n {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+        let pages_before_delete = table.pager.pages.len();
+
+        for id in 0..n {
+            table.delete_row(id).unwrap();
+        }
+        assert_eq!(table.select_row().len(), 0);
+
+        for id in 0..n {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+
+        // the freed leaves from the full delete pass should have been
+        // reclaimed rather than the file growing to hold a second copy
+        assert!(table.pager.pages.len() <= pages_before_delete + 1);
+        let ids: Vec<u32> = table.select_row().iter().map(|row| row.id).collect();
+        assert_eq!(ids, (0..n).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn allocate_page_zeroes_a_page_reused_from_the_free_list() {
+        let mut table = test_table();
+
+        let page_id = table.pager.allocate_page().unwrap();
+        table.pager.load_page(page_id).unwrap().bytes = [0xAB; PAGE_SIZE];
+        table.pager.free_page(page_id).unwrap();
+
+        let reused_id = table.pager.allocate_page().unwrap();
+        assert_eq!(reused_id, page_id);
+        assert_eq!(table.pager.load_page(reused_id).unwrap().bytes, [0u8; PAGE_SIZE]);
+    }
+
+    #[test]
+    fn select_page_walks_the_whole_table_without_gaps_or_duplicates() {
+        let mut table = test_table();
+
+        let n = 2000u32;
+        for id in (0..n).rev() {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut after = None;
+        loop {
+            let (rows, page_info) = table.select_page(37, after).unwrap();
+            assert!(rows.len() <= 37);
+            seen.extend(rows.iter().map(|row| row.id));
+            match page_info.next_cursor {
+                Some(cursor) if page_info.has_next_page => after = Some(decode_cursor(&cursor).unwrap()),
+                _ => break,
+            }
+        }
+
+        let expected: Vec<u32> = (0..n).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn select_where_between_finds_exactly_the_rows_in_range_and_skips_other_pages() {
+        let mut table = test_table();
+
+        let n = 2000u32;
+        for id in (0..n).rev() {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+
+        let (rows, skipped) = table.select_where_between(500, 509).unwrap();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, (500..=509).collect::<Vec<u32>>());
+        assert!(skipped > 0, "a narrow range should skip most leaf pages");
+
+        let (rows, _) = table.select_where_between(1_000_000, 2_000_000).unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn select_where_between_includes_leaves_past_the_zone_map_capacity() {
+        let mut table = test_table();
+
+        // enough rows to overflow the fixed-size zone map on page 0; leaves
+        // past its capacity must still be scanned, not silently dropped
+        let n = 6000u32;
+        for id in 0..n {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+        }
+
+        let (rows, _) = table.select_where_between(0, n - 1).unwrap();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, (0..n).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn select_where_between_never_loads_a_skipped_page_from_disk() {
+        let dbfile = tempfile().expect("Failed to create tempfile");
+        let journalfile = tempfile().expect("Failed to create tempfile");
+        {
+            let mut table = Table::new(Pager::new(
+                Box::new(dbfile.try_clone().expect("Failed to clone tempfile")),
+                Box::new(journalfile.try_clone().expect("Failed to clone tempfile")),
+            ));
+            let n = 2000u32;
+            for id in (0..n).rev() {
+                table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
+            }
+            table.pager.sync().unwrap();
+        }
+
+        // reopen with an empty in-memory cache so a leaf is only resident
+        // in `pager.pages` if something actually read it off disk
+        let mut table = Table::new(Pager::new(
+            Box::new(dbfile.try_clone().expect("Failed to clone tempfile")),
+            Box::new(journalfile.try_clone().expect("Failed to clone tempfile")),
+        ));
+
+        let (rows, skipped) = table.select_where_between(500, 509).unwrap();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, (500..=509).collect::<Vec<u32>>());
+        assert!(skipped > 0);
+
+        let out_of_range_leaf = table
+            .pager
+            .zone_map_entries()
+            .unwrap()
+            .into_iter()
+            .find(|&(_, min, max)| max < 500 || min > 509)
+            .map(|(page_id, _, _)| page_id)
+            .expect("at least one leaf should be entirely outside the range");
+        assert!(
+            table
+                .pager
+                .pages
+                .get(out_of_range_leaf)
+                .is_none_or(Option::is_none),
+            "a leaf the zone map rules out should never be read off disk"
+        );
+    }
+
+    #[test]
+    fn select_where_id_equals_finds_rows_past_the_zone_map_capacity() {
+        let mut table = test_table();
+
+        let n = 6000u32;
+        for id in 0..n {
+            table.insert_row(&Row::new(id, "foo", "bar")).unwrap();
         }
-        let res = table.insert_row(&Row::new(max, "foo", "bar"));
-        assert_eq!(res, Err("Table is full"));
+
+        let out = execute_statment(
+            Statement::SelectWhere {
+                lo: n - 1,
+                hi: n - 1,
+            },
+            &mut table,
+        )
+        .unwrap();
+        assert!(out.contains(&format!("id: {}", n - 1)), "{}", out);
+        assert!(out.contains("1 row(s)"), "{}", out);
+    }
+
+    #[test]
+    fn select_where_username_uses_the_index_created_on_it() {
+        let mut table = test_table();
+
+        table.insert_row(&Row::new(1, "alice", "alice@example.com")).unwrap();
+        table.insert_row(&Row::new(2, "bob", "bob@example.com")).unwrap();
+        table.create_index(IndexedColumn::Username).unwrap();
+        table.insert_row(&Row::new(3, "carol", "carol@example.com")).unwrap();
+
+        let rows = table.select_where_column_equals(IndexedColumn::Username, "bob").unwrap();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![2]);
+
+        let rows = table.select_where_column_equals(IndexedColumn::Username, "carol").unwrap();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![3]);
+
+        let rows = table.select_where_column_equals(IndexedColumn::Username, "dave").unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn delete_row_removes_its_slot_from_every_index() {
+        let mut table = test_table();
+
+        table.create_index(IndexedColumn::Username).unwrap();
+        table.insert_row(&Row::new(5, "alice", "alice@example.com")).unwrap();
+        table.delete_row(5).unwrap();
+        table.insert_row(&Row::new(5, "alice", "alice@example.com")).unwrap();
+
+        let rows = table.select_where_column_equals(IndexedColumn::Username, "alice").unwrap();
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![5]);
+    }
+
+    #[test]
+    fn create_index_rejects_a_duplicate_and_select_where_rejects_a_missing_index() {
+        let mut table = test_table();
+
+        assert_eq!(
+            table.select_where_column_equals(IndexedColumn::Username, "alice"),
+            Err("no index on this column")
+        );
+
+        table.create_index(IndexedColumn::Username).unwrap();
+        assert_eq!(
+            table.create_index(IndexedColumn::Username),
+            Err("index already exists")
+        );
+    }
+
+    #[test]
+    fn select_page_rejects_page_size_above_the_maximum() {
+        let mut table = test_table();
+
+        assert_eq!(
+            table.select_page(MAX_PAGE_SIZE + 1, None).err(),
+            Some("requested page size exceeds maximum allowed")
+        );
     }
 
     #[test]
     fn persistance() {
-        let tempfile = tempfile().expect("Failed to create tempfile");
+        let dbfile = tempfile().expect("Failed to create tempfile");
+        let journalfile = tempfile().expect("Failed to create tempfile");
         {
-            let mut table = Table::new(Pager::new(Box::new(
-                tempfile.try_clone().expect("Failed to clone tempfile"),
-            )));
+            let mut table = Table::new(Pager::new(
+                Box::new(dbfile.try_clone().expect("Failed to clone tempfile")),
+                Box::new(journalfile.try_clone().expect("Failed to clone tempfile")),
+            ));
 
             let rows = table.select_row();
             assert_eq!(rows.len(), 0);
@@ -497,9 +2639,10 @@ mod tests {
             drop(table);
         }
         {
-            let mut table = Table::new(Pager::new(Box::new(
-                tempfile.try_clone().expect("Failed to clone tempfile"),
-            )));
+            let mut table = Table::new(Pager::new(
+                Box::new(dbfile.try_clone().expect("Failed to clone tempfile")),
+                Box::new(journalfile.try_clone().expect("Failed to clone tempfile")),
+            ));
 
             let rows = table.select_row();
             assert_eq!(rows.len(), 1);
@@ -509,15 +2652,26 @@ mod tests {
     #[test]
     fn all() {
         let filename = "/tmp/c.db".to_string();
+        // duplicate keys are now rejected, so a stale file from a previous
+        // run (which would otherwise already contain ids 0..2) must go.
+        let _ = std::fs::remove_file(&filename);
+        let _ = std::fs::remove_file(format!("{}.journal", filename));
         let file = OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
             .truncate(false)
-            .open(filename)
+            .open(&filename)
+            .unwrap();
+        let journal = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(format!("{}.journal", filename))
             .unwrap();
 
-        let pager = Pager::new(Box::new(file));
+        let pager = Pager::new(Box::new(file), Box::new(journal));
         let mut table = Table::new(pager);
         table.insert_row(&Row::new(0, "foo", "bar")).unwrap();
         table.insert_row(&Row::new(1, "foo", "bar")).unwrap();
@@ -527,4 +2681,76 @@ mod tests {
             println!("s - {:?}", row);
         }
     }
+
+    #[test]
+    fn rollback_restores_rows_inserted_since_begin() {
+        let mut table = test_table();
+
+        table.insert_row(&Row::new(1, "foo", "bar")).unwrap();
+
+        table.begin().unwrap();
+        table.insert_row(&Row::new(2, "foo", "bar")).unwrap();
+        table.delete_row(1).unwrap();
+        table.rollback().unwrap();
+
+        let ids: Vec<u32> = table.select_row().iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![1]);
+        assert!(!table.in_transaction());
+    }
+
+    #[test]
+    fn commit_makes_rows_inserted_since_begin_permanent() {
+        let mut table = test_table();
+
+        table.begin().unwrap();
+        table.insert_row(&Row::new(1, "foo", "bar")).unwrap();
+        table.commit().unwrap();
+
+        let ids: Vec<u32> = table.select_row().iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![1]);
+        assert!(!table.in_transaction());
+    }
+
+    #[test]
+    fn begin_and_commit_reject_being_called_twice() {
+        let mut table = test_table();
+
+        assert_eq!(table.commit(), Err("no transaction is active"));
+        assert_eq!(table.rollback(), Err("no transaction is active"));
+
+        table.begin().unwrap();
+        assert_eq!(table.begin(), Err("a transaction is already active"));
+        table.commit().unwrap();
+    }
+
+    #[test]
+    fn reopening_after_a_half_finished_transaction_undoes_it() {
+        let dbfile = tempfile().expect("Failed to create tempfile");
+        let journalfile = tempfile().expect("Failed to create tempfile");
+        {
+            let mut table = Table::new(Pager::new(
+                Box::new(dbfile.try_clone().expect("Failed to clone tempfile")),
+                Box::new(journalfile.try_clone().expect("Failed to clone tempfile")),
+            ));
+            table.insert_row(&Row::new(1, "foo", "bar")).unwrap();
+            table.pager.sync().unwrap();
+
+            // simulate a crash mid-transaction: the journal has a pre-image
+            // recorded and the page has been overwritten, but neither
+            // `commit` nor `rollback` ever ran before the pager is dropped
+            table.begin().unwrap();
+            table.delete_row(1).unwrap();
+            table.pager.sync().unwrap();
+            drop(table);
+        }
+        {
+            let mut table = Table::new(Pager::new(
+                Box::new(dbfile.try_clone().expect("Failed to clone tempfile")),
+                Box::new(journalfile.try_clone().expect("Failed to clone tempfile")),
+            ));
+
+            let ids: Vec<u32> = table.select_row().iter().map(|row| row.id).collect();
+            assert_eq!(ids, vec![1]);
+        }
+    }
 }